@@ -0,0 +1,44 @@
+// SPDX-License-Identifier: UNLICENSED
+pragma solidity ^0.8.13;
+
+import {CommonBase} from "forge-std/Base.sol";
+import {StdCheats} from "forge-std/StdCheats.sol";
+import {StdUtils} from "forge-std/StdUtils.sol";
+import {console} from "forge-std/console.sol";
+import "{import_path}";
+
+contract {contract_name}Handler is CommonBase, StdCheats, StdUtils {
+    {contract_name} public {instance_name};
+
+    address[] public actors;
+    address internal currentActor;
+
+    // Ghost variables, track any additional state the invariants need to assert on here.
+    mapping(string => uint256) public calls;
+
+    modifier useActor(uint256 actorIndexSeed) {
+        currentActor = actors[bound(actorIndexSeed, 0, actors.length - 1)];
+        vm.startPrank(currentActor);
+        _;
+        vm.stopPrank();
+    }
+
+    modifier countCall(string memory key) {
+        calls[key]++;
+        _;
+    }
+
+    constructor({contract_name} _{instance_name}) {
+        {instance_name} = _{instance_name};
+
+        for (uint256 i = 0; i < 3; i++) {
+            actors.push(makeAddr(string.concat("actor", vm.toString(i))));
+        }
+    }
+{handler_functions}
+    function callSummary() external view {
+        console.log("Call summary:");
+        console.log("-------------------");
+{call_summary}
+    }
+}
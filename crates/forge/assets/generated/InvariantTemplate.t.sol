@@ -0,0 +1,25 @@
+// SPDX-License-Identifier: UNLICENSED
+pragma solidity ^0.8.13;
+
+import {Test} from "forge-std/Test.sol";
+import "{import_path}";
+import {{contract_name}Handler} from "./handlers/{contract_name}Handler.sol";
+
+contract Invariant{contract_name}Test is Test {
+    {contract_name} public {instance_name};
+    {contract_name}Handler public handler;
+
+    function setUp() public {
+{constructor_args}        {instance_name} = new {contract_name}({constructor_call_args});
+        handler = new {contract_name}Handler({instance_name});
+
+        bytes4[] memory selectors = new bytes4[]({selector_count});
+{target_selectors}
+        targetSelector(FuzzSelector({addr: address(handler), selectors: selectors}));
+        targetContract(address(handler));
+    }
+
+    function invariant_callSummary() public view {
+        handler.callSummary();
+    }
+}
@@ -0,0 +1,507 @@
+use super::format_identifier;
+use alloy_json_abi::{Constructor, Function, InternalType, JsonAbi, Param, StateMutability};
+use clap::Parser;
+use eyre::Result;
+use foundry_cli::opts::{CompilerArgs, CoreBuildArgs, ProjectPathsArgs};
+use foundry_common::{compile::compile_target, fs};
+use foundry_compilers::artifacts::{output_selection::ContractOutputSelection, BytecodeObject};
+use std::{collections::HashSet, fmt::Write};
+use yansi::Paint;
+
+/// CLI arguments for `forge generate handler`.
+#[derive(Debug, Parser)]
+pub struct GenerateHandlerArgs {
+    /// Contract name for handler generation.
+    #[arg(long, short, value_name = "CONTRACT_NAME")]
+    pub contract_name: String,
+
+    #[command(flatten)]
+    pub project_paths: ProjectPathsArgs,
+}
+
+impl GenerateHandlerArgs {
+    pub fn run(self) -> Result<()> {
+        let build_args = CoreBuildArgs {
+            project_paths: self.project_paths,
+            compiler: CompilerArgs {
+                extra_output: vec![ContractOutputSelection::Abi],
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        // Compile the target contract and fetch its ABI from the artifacts.
+        let project = build_args.project()?;
+        let target_path = project.find_contract_path(&self.contract_name)?;
+        let output = compile_target(&target_path, &project, true)?;
+        let artifact = output.find_first(&self.contract_name).ok_or_else(|| {
+            eyre::eyre!(
+                "Could not find artifact `{}` in the compiled artifacts",
+                self.contract_name
+            )
+        })?;
+        let abi = artifact.abi.as_ref().ok_or_else(|| eyre::eyre!("Unable to fetch abi"))?;
+
+        let contract_name = self.contract_name.as_str();
+        let is_deployable = artifact.bytecode.as_ref().is_some_and(|b| match &b.object {
+            BytecodeObject::Bytecode(b) => !b.is_empty(),
+            BytecodeObject::Unlinked(_) => true,
+        });
+        if !is_deployable {
+            eyre::bail!("`{contract_name}` is abstract or an interface; pass a concrete contract");
+        }
+
+        let instance_name = format_identifier(contract_name, false);
+        let handlers = collect_handlers(abi);
+        if handlers.is_empty() {
+            eyre::bail!("Contract `{contract_name}` has no state-mutating functions to target");
+        }
+
+        // Sources are imported relative to the project root.
+        let import_path = target_path
+            .strip_prefix(&project.paths.root)
+            .unwrap_or(&target_path)
+            .to_string_lossy()
+            .replace('\\', "/");
+
+        let handler_content =
+            render_handler(contract_name, &instance_name, &import_path, &handlers);
+        let invariant_content =
+            render_invariant_test(contract_name, &instance_name, &import_path, abi, &handlers);
+
+        // Create the handlers directory if it doesn't exist.
+        let handlers_dir = project.paths.tests.join("handlers");
+        fs::create_dir_all(&handlers_dir)?;
+
+        let handler_file_path = handlers_dir.join(format!("{contract_name}Handler.sol"));
+        fs::write(&handler_file_path, handler_content)?;
+        println!("{} handler file: {}", "Generated".green(), handler_file_path.display());
+
+        let invariant_file_path =
+            project.paths.tests.join(format!("Invariant{contract_name}.t.sol"));
+        fs::write(&invariant_file_path, invariant_content)?;
+        println!("{} invariant test file: {}", "Generated".green(), invariant_file_path.display());
+
+        Ok(())
+    }
+}
+
+/// Identifiers declared or used by the handler template that the parameters of generated wrapper
+/// functions must not redeclare.
+const RESERVED_NAMES: &[&str] = &[
+    "actorIndexSeed",
+    "msgValue",
+    "currentActor",
+    "actors",
+    "calls",
+    "callSummary",
+    "useActor",
+    "countCall",
+    "vm",
+    "bound",
+    "console",
+];
+
+/// Identifiers declared by the invariant test template, in addition to the reserved names.
+const INVARIANT_TEST_NAMES: &[&str] = &["handler", "selectors", "setUp"];
+
+/// A handler wrapper around a single state-mutating function of the target contract.
+struct Handler<'a> {
+    /// Name of the wrapper function, unique within the handler contract.
+    name: String,
+    function: &'a Function,
+}
+
+/// Collects one handler for every payable or non-payable function in the ABI.
+///
+/// Wrappers are prefixed with `handler_` so that they never clash with members of the handler
+/// template or the forge-std contracts it inherits, and overloaded functions are suffixed with
+/// their index so that every wrapper has a distinct name and can be referenced with `.selector`
+/// from the invariant test.
+fn collect_handlers(abi: &JsonAbi) -> Vec<Handler<'_>> {
+    let mut taken = HashSet::new();
+    let mut handlers = Vec::new();
+    for (name, overloads) in &abi.functions {
+        let mutating = overloads
+            .iter()
+            .filter(|f| {
+                matches!(f.state_mutability, StateMutability::NonPayable | StateMutability::Payable)
+            })
+            .collect::<Vec<_>>();
+        let overloaded = mutating.len() > 1;
+        for (i, function) in mutating.into_iter().enumerate() {
+            let base =
+                if overloaded { format!("handler_{name}_{i}") } else { format!("handler_{name}") };
+            handlers.push(Handler { name: unique_name(base, &mut taken), function });
+        }
+    }
+    handlers
+}
+
+/// Returns `base`, suffixed with underscores until it no longer clashes with a taken name, and
+/// marks the result as taken.
+fn unique_name(base: String, taken: &mut HashSet<String>) -> String {
+    let mut name = base;
+    while taken.contains(&name) {
+        name.push('_');
+    }
+    taken.insert(name.clone());
+    name
+}
+
+/// Returns the local names used for `inputs`, with unnamed parameters named after their index and
+/// clashes with `reserved` names resolved.
+fn param_names<'a>(inputs: &[Param], reserved: impl IntoIterator<Item = &'a str>) -> Vec<String> {
+    let mut taken = reserved.into_iter().map(String::from).collect::<HashSet<_>>();
+    inputs
+        .iter()
+        .enumerate()
+        .map(|(i, param)| {
+            let base = if param.name.is_empty() { format!("arg{i}") } else { param.name.clone() };
+            unique_name(base, &mut taken)
+        })
+        .collect()
+}
+
+/// Returns the Solidity type of the parameter, including its data location if required.
+///
+/// The internal type is preferred so that structs, enums and contract types resolve to the
+/// declarations imported from the target source file.
+fn param_type(param: &Param) -> String {
+    let ty = match &param.internal_type {
+        Some(InternalType::AddressPayable(ty)) | Some(InternalType::Contract(ty)) => ty.clone(),
+        Some(InternalType::Enum { contract, ty }) | Some(InternalType::Struct { contract, ty }) => {
+            match contract {
+                Some(contract) => format!("{contract}.{ty}"),
+                None => ty.clone(),
+            }
+        }
+        _ => param.ty.clone(),
+    };
+    let is_reference = ty.ends_with(']') ||
+        matches!(ty.as_str(), "bytes" | "string") ||
+        matches!(param.internal_type, Some(InternalType::Struct { .. }));
+    if is_reference {
+        format!("{ty} memory")
+    } else {
+        ty
+    }
+}
+
+/// Renders the handler wrapper function for a single target function.
+fn render_handler_function(handler: &Handler<'_>, instance_name: &str) -> String {
+    let Handler { name, function } = handler;
+    let is_payable = function.state_mutability == StateMutability::Payable;
+    let names =
+        param_names(&function.inputs, RESERVED_NAMES.iter().copied().chain([instance_name]));
+
+    let mut params = vec!["uint256 actorIndexSeed".to_string()];
+    if is_payable {
+        params.push("uint256 msgValue".to_string());
+    }
+    params.extend(
+        function
+            .inputs
+            .iter()
+            .zip(&names)
+            .map(|(param, name)| format!("{} {name}", param_type(param))),
+    );
+
+    let mut out = String::new();
+    writeln!(
+        out,
+        "    function {name}({}) public useActor(actorIndexSeed) countCall(\"{name}\") {{",
+        params.join(", ")
+    )
+    .unwrap();
+
+    // Bound stubs for the integer inputs, left for the user to tighten.
+    let bounds = function
+        .inputs
+        .iter()
+        .zip(&names)
+        .filter_map(|(param, name)| render_bound(param, name))
+        .collect::<Vec<_>>();
+    if !bounds.is_empty() {
+        writeln!(
+            out,
+            "        // TODO: tighten the bounds of the integer inputs to the ranges the target expects."
+        )
+        .unwrap();
+        for bound in bounds {
+            writeln!(out, "        // {bound}").unwrap();
+        }
+    }
+
+    let args = names.join(", ");
+    if is_payable {
+        writeln!(out, "        msgValue = bound(msgValue, 0, 100 ether);").unwrap();
+        writeln!(out, "        vm.deal(currentActor, msgValue);").unwrap();
+        writeln!(out, "        {instance_name}.{}{{value: msgValue}}({args});", function.name)
+            .unwrap();
+    } else {
+        writeln!(out, "        {instance_name}.{}({args});", function.name).unwrap();
+    }
+    writeln!(out, "        // TODO: update ghost variables.").unwrap();
+    writeln!(out, "    }}").unwrap();
+    out
+}
+
+/// Returns a `bound` statement for the input if it is a `uintN` or `intN`, clamped to the full
+/// range of its type.
+fn render_bound(param: &Param, name: &str) -> Option<String> {
+    if matches!(param.internal_type, Some(InternalType::Enum { .. })) {
+        return None
+    }
+    let ty = param.ty.as_str();
+    let (is_signed, bits) = match ty.strip_prefix("uint") {
+        Some(bits) => (false, bits),
+        None => (true, ty.strip_prefix("int")?),
+    };
+    if bits.is_empty() || !bits.bytes().all(|b| b.is_ascii_digit()) {
+        return None
+    }
+    let (min, max) = if is_signed {
+        (format!("type({ty}).min"), format!("type({ty}).max"))
+    } else {
+        ("0".to_string(), format!("type({ty}).max"))
+    };
+    Some(if bits == "256" {
+        format!("{name} = bound({name}, {min}, {max});")
+    } else {
+        format!("{name} = {ty}(bound({name}, {min}, {max}));")
+    })
+}
+
+fn render_handler(
+    contract_name: &str,
+    instance_name: &str,
+    import_path: &str,
+    handlers: &[Handler<'_>],
+) -> String {
+    let handler_functions = handlers.iter().fold(String::new(), |mut out, handler| {
+        out.push('\n');
+        out.push_str(&render_handler_function(handler, instance_name));
+        out
+    });
+    let call_summary = handlers
+        .iter()
+        .map(|Handler { name, .. }| format!("        console.log(\"{name}\", calls[\"{name}\"]);"))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let content = include_str!("../../../assets/generated/HandlerTemplate.sol");
+    content
+        .replace("{contract_name}", contract_name)
+        .replace("{instance_name}", instance_name)
+        .replace("{import_path}", import_path)
+        .replace("{handler_functions}", &handler_functions)
+        .replace("{call_summary}", &call_summary)
+}
+
+/// Renders typed locals for the constructor inputs of the target, along with the argument list
+/// passed to its deployment.
+///
+/// The locals are left at their default values for the user to fill in.
+fn render_constructor_args(
+    constructor: Option<&Constructor>,
+    instance_name: &str,
+) -> (String, String) {
+    let Some(constructor) = constructor.filter(|c| !c.inputs.is_empty()) else {
+        return (String::new(), String::new())
+    };
+    let names = param_names(
+        &constructor.inputs,
+        RESERVED_NAMES.iter().chain(INVARIANT_TEST_NAMES).copied().chain([instance_name]),
+    );
+
+    let mut locals = String::from("        // TODO: set the constructor arguments.\n");
+    for (param, name) in constructor.inputs.iter().zip(&names) {
+        writeln!(locals, "        {} {name};", param_type(param)).unwrap();
+    }
+    (locals, names.join(", "))
+}
+
+fn render_invariant_test(
+    contract_name: &str,
+    instance_name: &str,
+    import_path: &str,
+    abi: &JsonAbi,
+    handlers: &[Handler<'_>],
+) -> String {
+    let (constructor_args, constructor_call_args) =
+        render_constructor_args(abi.constructor.as_ref(), instance_name);
+    let target_selectors = handlers
+        .iter()
+        .enumerate()
+        .map(|(i, Handler { name, .. })| {
+            format!("        selectors[{i}] = {contract_name}Handler.{name}.selector;")
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let content = include_str!("../../../assets/generated/InvariantTemplate.t.sol");
+    content
+        .replace("{contract_name}", contract_name)
+        .replace("{instance_name}", instance_name)
+        .replace("{import_path}", import_path)
+        .replace("{constructor_args}", &constructor_args)
+        .replace("{constructor_call_args}", &constructor_call_args)
+        .replace("{selector_count}", &handlers.len().to_string())
+        .replace("{target_selectors}", &target_selectors)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn abi(signatures: &[&str]) -> JsonAbi {
+        JsonAbi::parse(signatures.iter().copied()).unwrap()
+    }
+
+    fn handler_names(abi: &JsonAbi) -> Vec<String> {
+        collect_handlers(abi).into_iter().map(|h| h.name).collect()
+    }
+
+    fn param(ty: &str, internal_type: &str) -> Param {
+        let components = if ty.starts_with("tuple") {
+            serde_json::json!([{ "name": "a", "type": "uint256", "internalType": "uint256" }])
+        } else {
+            serde_json::json!([])
+        };
+        serde_json::from_value(serde_json::json!({
+            "name": "x",
+            "type": ty,
+            "internalType": internal_type,
+            "components": components,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn skips_view_functions() {
+        let abi = abi(&[
+            "function increment()",
+            "function number() view returns (uint256)",
+            "function double(uint256) pure returns (uint256)",
+        ]);
+        assert_eq!(handler_names(&abi), ["handler_increment"]);
+    }
+
+    #[test]
+    fn disambiguates_overloads() {
+        let abi = abi(&[
+            "function set(uint256 x)",
+            "function set(uint256 x, uint256 y)",
+            "function set_0()",
+        ]);
+        assert_eq!(handler_names(&abi), ["handler_set_0", "handler_set_1", "handler_set_0_"]);
+    }
+
+    #[test]
+    fn prefixes_inherited_names() {
+        let abi = abi(&[
+            "function deal(address to, uint256 amount)",
+            "function skip(uint256 time)",
+            "function stdstore()",
+            "function callSummary()",
+        ]);
+        assert_eq!(
+            handler_names(&abi),
+            ["handler_callSummary", "handler_deal", "handler_skip", "handler_stdstore"]
+        );
+    }
+
+    #[test]
+    fn renames_reserved_params() {
+        let abi =
+            abi(&["function deposit(uint256 msgValue, address actorIndexSeed, bytes) payable"]);
+        let handlers = collect_handlers(&abi);
+        let rendered = render_handler_function(&handlers[0], "vault");
+        assert!(rendered.contains(
+            "function handler_deposit(uint256 actorIndexSeed, uint256 msgValue, uint256 msgValue_, address actorIndexSeed_, bytes memory arg2)"
+        ));
+        assert!(rendered.contains("// msgValue_ = bound(msgValue_, 0, type(uint256).max);"));
+        assert!(
+            rendered.contains("vault.deposit{value: msgValue}(msgValue_, actorIndexSeed_, arg2);")
+        );
+    }
+
+    #[test]
+    fn resolves_internal_types() {
+        assert_eq!(param_type(&param("tuple[]", "struct Foo.Bar[]")), "Foo.Bar[] memory");
+        assert_eq!(param_type(&param("tuple", "struct Bar")), "Bar memory");
+        assert_eq!(param_type(&param("uint8", "enum Foo.Kind")), "Foo.Kind");
+        assert_eq!(param_type(&param("uint8[]", "enum Foo.Kind[]")), "Foo.Kind[] memory");
+        assert_eq!(param_type(&param("address", "contract IERC20")), "IERC20");
+        assert_eq!(param_type(&param("address", "address payable")), "address payable");
+        assert_eq!(param_type(&param("uint256[2]", "uint256[2]")), "uint256[2] memory");
+        assert_eq!(param_type(&param("string", "string")), "string memory");
+        assert_eq!(param_type(&param("bytes32", "bytes32")), "bytes32");
+    }
+
+    #[test]
+    fn renders_integer_bounds() {
+        let bound = |ty: &str, internal_type: &str| render_bound(&param(ty, internal_type), "x");
+        assert_eq!(bound("uint256", "uint256").unwrap(), "x = bound(x, 0, type(uint256).max);");
+        assert_eq!(bound("uint8", "uint8").unwrap(), "x = uint8(bound(x, 0, type(uint8).max));");
+        assert_eq!(
+            bound("int256", "int256").unwrap(),
+            "x = bound(x, type(int256).min, type(int256).max);"
+        );
+        assert_eq!(
+            bound("int64", "int64").unwrap(),
+            "x = int64(bound(x, type(int64).min, type(int64).max));"
+        );
+        assert_eq!(bound("uint8", "enum Foo.Kind"), None);
+        assert_eq!(bound("uint256[]", "uint256[]"), None);
+        assert_eq!(bound("address", "address"), None);
+    }
+
+    #[test]
+    fn renders_bounded_payable_wrapper() {
+        let abi = abi(&["function deposit(uint256 amount, uint128 limit, bytes data) payable"]);
+        let handlers = collect_handlers(&abi);
+        let rendered = render_handler_function(&handlers[0], "vault");
+        assert_eq!(
+            rendered,
+            r#"    function handler_deposit(uint256 actorIndexSeed, uint256 msgValue, uint256 amount, uint128 limit, bytes memory data) public useActor(actorIndexSeed) countCall("handler_deposit") {
+        // TODO: tighten the bounds of the integer inputs to the ranges the target expects.
+        // amount = bound(amount, 0, type(uint256).max);
+        // limit = uint128(bound(limit, 0, type(uint128).max));
+        msgValue = bound(msgValue, 0, 100 ether);
+        vm.deal(currentActor, msgValue);
+        vault.deposit{value: msgValue}(amount, limit, data);
+        // TODO: update ghost variables.
+    }
+"#
+        );
+    }
+
+    #[test]
+    fn renders_target_selectors() {
+        let abi = abi(&["function increment()", "function setNumber(uint256)"]);
+        let handlers = collect_handlers(&abi);
+        let rendered =
+            render_invariant_test("Counter", "counter", "src/Counter.sol", &abi, &handlers);
+        assert!(rendered.contains("import \"src/Counter.sol\";"));
+        assert!(rendered.contains("counter = new Counter();"));
+        assert!(rendered.contains("new bytes4[](2);"));
+        assert!(rendered.contains("selectors[0] = CounterHandler.handler_increment.selector;"));
+        assert!(rendered.contains("selectors[1] = CounterHandler.handler_setNumber.selector;"));
+    }
+
+    #[test]
+    fn renders_constructor_args() {
+        let abi = abi(&["constructor(string name, uint256 handler, address)", "function mint()"]);
+        let handlers = collect_handlers(&abi);
+        let rendered = render_invariant_test("Token", "token", "src/Token.sol", &abi, &handlers);
+        assert!(rendered.contains(
+            "        // TODO: set the constructor arguments.
+        string memory name;
+        uint256 handler_;
+        address arg2;
+        token = new Token(name, handler_, arg2);"
+        ));
+    }
+}
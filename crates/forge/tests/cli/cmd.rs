@@ -1713,3 +1713,73 @@ forgetest_init!(can_inspect_counter_pretty, |prj, cmd| {
 }"
     );
 });
+
+// checks that the generated handler and invariant test compile against forge-std
+forgetest_init!(can_generate_handler, |prj, cmd| {
+    cmd.args(["generate", "handler", "-c", "Counter"]).root_arg();
+    cmd.assert_non_empty_stdout();
+    assert!(prj.root().join("test/handlers/CounterHandler.sol").exists());
+    assert!(prj.root().join("test/InvariantCounter.t.sol").exists());
+
+    cmd.forge_fuse().arg("build").root_arg();
+    cmd.assert_non_empty_stdout();
+});
+
+// checks that the generated handler compiles for constructor args, overloads, payable functions,
+// struct parameters and names inherited from forge-std
+forgetest_init!(can_generate_handler_for_complex_target, |prj, cmd| {
+    prj.add_source(
+        "Vault",
+        r#"
+contract Vault {
+    struct Order {
+        address owner;
+        uint256 amount;
+    }
+
+    string public name;
+    address public owner;
+
+    constructor(string memory _name, address _owner) {
+        name = _name;
+        owner = _owner;
+    }
+
+    function deposit(uint256 amount) external payable {}
+    function set(uint256 x) external {}
+    function set(uint256 x, uint8 y) external {}
+    function place(Order calldata order) external {}
+    function deal(address to, uint256 amount) external {}
+    function skip(uint256 time) external {}
+}
+   "#,
+    )
+    .unwrap();
+
+    cmd.args(["generate", "handler", "-c", "Vault"]).root_arg();
+    cmd.assert_non_empty_stdout();
+
+    cmd.forge_fuse().arg("build").root_arg();
+    cmd.assert_non_empty_stdout();
+});
+
+// checks that handlers are not generated for contracts that cannot be deployed
+forgetest!(cannot_generate_handler_for_interface, |prj, cmd| {
+    prj.add_source(
+        "IVault",
+        r"
+interface IVault {
+    function deposit(uint256 amount) external;
+}
+   ",
+    )
+    .unwrap();
+
+    cmd.args(["generate", "handler", "-c", "IVault"]).root_arg();
+    let stderr = cmd.stderr_lossy();
+    assert!(
+        stderr.contains("`IVault` is abstract or an interface; pass a concrete contract"),
+        "{stderr}"
+    );
+    assert!(!prj.root().join("test/InvariantIVault.t.sol").exists());
+});